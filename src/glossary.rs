@@ -1,86 +1,128 @@
 use std::collections::HashMap;
 
-use super::{WordArena, AnnotatedString, Word};
+use super::{WordArena, AnnotatedString, Word, PartOfSpeech};
+use PartOfSpeech::*;
 
 pub(super) type Glossary = HashMap<Word, Option<AnnotatedString>>;
 
 pub const RANDOM_SIGNAL: &str = "::::";
 
-/// Words that are defined in the global glossary.
-const DEFINED: &[(&str, &str)] = &[
-    ("glossary",      "The part of a book with definitions of words used in it."),
-    ("words",         "See 'word.'"),
-    ("word",          "You're reading them."),
-    ("definitions",   "See 'definition.'"),
-    ("definition",    "You're reading one."),
-    ("book",          "You're reading one."),
-    ("reading",       "You're doing it."),
-    ("index",         "The part of a book that indicates where words are used."),
-    ("indicates",     "See 'indicate.'"),
-    ("indicate",      "I'm doing it."),
-    ("time",          "You're going through it."),
-    ("end",           "I doubt you'll get there."),
-    ("table",         "An organized list."),
-    ("list",          "Nothing, or cons."),
-    ("cons",          "Something, and a list."),
-    ("organized",     "I wish I knew."),
-    ("contents",      "What's in."),
-    ("chapter",       "The central component of a book."),
-    ("central",       "<insert in-joke>"),
-    ("joke",          "What's made more confusing by being read out-of-order?"),
-    ("confusing",     "See this book."),
-    ("read",          "What you're supposed to do to a book, but shouldn't to this one."),
-    ("afterword",     "Words which are said after."),
-    ("figures",       "People, numbers, and drawings."),
-    ("people",        "Dumb, panicky dangerous animals and you know it."),
-    ("numbers",       "See list of figures for examples."),
-    ("drawings",      "The best kind of math."),
-    ("examples",      "See 'example.'"),
-    ("example",       "See 'examples' for an example."),
-    ("math",          "See 'numbers.'"),
-    ("you",           "You're being it."),
-    ("know",          "Getting philosophical, are we?"),
-    ("philosophical", "Silly and distracting in nature."),
-    ("fourwords",     "Four words, written forwards, comprising forewords."),
-    ("fourword",      "See 'fourwords.'"),
-    ("four",          "See 'three,' then add one."),
-    ("three",         "See 'two,' then add one."),
-    ("two",           "See 'one,' then add one."),
-    ("add",           "See 'math.'"),
-    ("one",           "Something."),
-    ("forwards",      "Not left. Not right."),
-    ("dedicated",     "Given a dedication."),
-    ("dedication",    "What one would need to write all these definitions."),
-    ("nanogenmo",     "It's this programming event - maybe you've heard of it?"),
-    ("programming",   "I'm doing it."),
-    ("maybe",         "Something or nothing."),
-    ("exception",     "It's supposed to mean something."),
-    ("random",        RANDOM_SIGNAL),
-    ("generated",     "See 'generation.'"),
-    ("generates",     "See 'generation.'"),
-    ("generation",    "It's happening."),
-    ("known",         "See 'know.'"),
-    ("theory",        "See 'math.'"),
-    ("issue",         "See my code for examples."),
-    ("code",          "You're why it exists."),
-    ("think",         "We all need to do it."),
-    ("look",          "You're doing it."),
-    ("probability",   "See 'math.'"),
-    ("find",          "You've done it!"),
-    ("suggestion",    "We all have them."),
+/// Words that are defined in the global glossary, tagged with the part of speech they're used as.
+const DEFINED: &[(&str, &str, PartOfSpeech)] = &[
+    ("glossary",      "The part of a book with definitions of words used in it.", Noun),
+    ("words",         "See 'word.'", Noun),
+    ("word",          "You're reading them.", Noun),
+    ("definitions",   "See 'definition.'", Noun),
+    ("definition",    "You're reading one.", Noun),
+    ("book",          "You're reading one.", Noun),
+    ("reading",       "You're doing it.", Verb),
+    ("index",         "The part of a book that indicates where words are used.", Noun),
+    ("indicates",     "See 'indicate.'", Verb),
+    ("indicate",      "I'm doing it.", Verb),
+    ("time",          "You're going through it.", Noun),
+    ("end",           "I doubt you'll get there.", Noun),
+    ("table",         "An organized list.", Noun),
+    ("list",          "Nothing, or cons.", Noun),
+    ("cons",          "Something, and a list.", Noun),
+    ("organized",     "I wish I knew.", Adjective),
+    ("contents",      "What's in.", Noun),
+    ("chapter",       "The central component of a book.", Noun),
+    ("central",       "<insert in-joke>", Adjective),
+    ("joke",          "What's made more confusing by being read out-of-order?", Noun),
+    ("confusing",     "See this book.", Adjective),
+    ("read",          "What you're supposed to do to a book, but shouldn't to this one.", Verb),
+    ("afterword",     "Words which are said after.", Noun),
+    ("figures",       "People, numbers, and drawings.", Noun),
+    ("people",        "Dumb, panicky dangerous animals and you know it.", Noun),
+    ("numbers",       "See list of figures for examples.", Noun),
+    ("drawings",      "The best kind of math.", Noun),
+    ("examples",      "See 'example.'", Noun),
+    ("example",       "See 'examples' for an example.", Noun),
+    ("math",          "See 'numbers.'", Noun),
+    ("you",           "You're being it.", Other),
+    ("know",          "Getting philosophical, are we?", Verb),
+    ("philosophical", "Silly and distracting in nature.", Adjective),
+    ("fourwords",     "Four words, written forwards, comprising forewords.", Noun),
+    ("fourword",      "See 'fourwords.'", Noun),
+    ("four",          "See 'three,' then add one.", Other),
+    ("three",         "See 'two,' then add one.", Other),
+    ("two",           "See 'one,' then add one.", Other),
+    ("add",           "See 'math.'", Verb),
+    ("one",           "Something.", Other),
+    ("forwards",      "Not left. Not right.", Adverb),
+    ("dedicated",     "Given a dedication.", Adjective),
+    ("dedication",    "What one would need to write all these definitions.", Noun),
+    ("nanogenmo",     "It's this programming event - maybe you've heard of it?", Noun),
+    ("programming",   "I'm doing it.", Verb),
+    ("maybe",         "Something or nothing.", Adverb),
+    ("exception",     "It's supposed to mean something.", Noun),
+    ("random",        RANDOM_SIGNAL, Adjective),
+    ("generated",     "See 'generation.'", Adjective),
+    ("generates",     "See 'generation.'", Verb),
+    ("generation",    "It's happening.", Noun),
+    ("known",         "See 'know.'", Adjective),
+    ("theory",        "See 'math.'", Noun),
+    ("issue",         "See my code for examples.", Noun),
+    ("code",          "You're why it exists.", Noun),
+    ("think",         "We all need to do it.", Verb),
+    ("look",          "You're doing it.", Verb),
+    ("probability",   "See 'math.'", Noun),
+    ("find",          "You've done it!", Verb),
+    ("suggestion",    "We all have them.", Noun),
+];
+/// Words that are left undefined, tagged with the part of speech they're used as.
+const UNDEFINED: &[(&str, PartOfSpeech)] = &[
+    ("the", Other), ("part", Noun), ("of", Other), ("a", Other), ("that", Other),
+    ("used", Verb), ("in", Other), ("it", Other), ("you're", Other), ("doing", Verb),
+    ("where", Other), ("see", Verb), ("with", Other), ("them", Other), ("i'm", Other),
+    ("are", Other), ("not", Adverb), ("given", Verb), ("this", Other), ("is", Other),
+    ("once", Adverb), ("upon", Other), ("going", Verb), ("through", Other), ("i", Other),
+    ("get", Verb), ("there", Other), ("doubt", Verb), ("you'll", Other), ("an", Other),
+    ("wish", Verb), ("knew", Verb), ("nil", Noun), ("something", Other), ("nothing", Other),
+    ("or", Other), ("and", Other), ("what's", Other), ("insert", Verb), ("component", Noun),
+    ("here", Adverb), ("academia", Noun), ("made", Verb), ("more", Adverb), ("by", Other),
+    ("being", Verb), ("what", Other), ("out", Adverb), ("supposed", Verb), ("to", Other),
+    ("order", Noun), ("do", Verb), ("but", Other), ("shouldn't", Other), ("which", Other),
+    ("said", Verb), ("after", Other), ("dumb", Adjective), ("panicky", Adjective),
+    ("best", Adjective), ("for", Other), ("dangerous", Adjective), ("animals", Noun),
+    ("kind", Noun), ("getting", Verb), ("silly", Adjective), ("distracting", Adjective),
+    ("nature", Noun), ("we", Other), ("written", Adjective), ("then", Adverb),
+    ("comprising", Verb), ("forewords", Noun), ("left", Other), ("right", Other),
+    ("all", Other), ("material", Noun), ("hello", Other), ("following", Adjective),
+    ("would", Other), ("need", Verb), ("write", Verb), ("these", Other), ("lucky", Adjective),
+    ("your", Other), ("p", Other), ("s", Other), ("fun", Noun), ("else", Other),
+    ("really", Adverb), ("about", Other), ("ids", Noun), ("was", Other), ("shared", Verb),
+    ("precisely", Adverb), ("than", Other), ("higher", Adjective), ("id", Noun),
+    ("section", Noun), ("sections", Noun), ("recommended", Verb), ("community", Noun),
+    ("it's", Other), ("event", Noun), ("you've", Other), ("heard", Verb), ("mean", Verb),
+    ("when", Other), ("trust", Verb), ("accuracy", Noun), ("happening", Verb), ("been", Other),
+    ("just", Adverb), ("could", Other), ("entire", Adjective), ("purpose", Noun),
+    ("did", Other), ("chose", Verb), ("happened", Verb), ("my", Other), ("exists", Verb),
+    ("why", Other), ("submitted", Verb), ("isn't", Other), ("low", Adjective),
+    ("pretty", Adverb), ("rs", Other), ("main", Adjective), ("line", Noun), ("wait", Verb),
+    ("can", Other), ("on", Other), ("appear", Verb), ("message", Noun), ("source", Noun),
+    ("book's", Other), ("into", Other), ("go", Verb), ("have", Verb), ("done", Verb),
+    ("playing", Verb), ("they're", Other), ("character", Noun), ("narrator", Noun),
+    ("reconcyl", Noun), ("author", Noun), ("text", Noun), ("reader", Noun), ("dear", Adjective),
+    // These only ever show up in the HTML/LaTeX renderers' markup, never in Markdown output.
+    ("h", Other), ("b", Other), ("li", Other), ("href", Other),
+    ("textbf", Other), ("item", Other), ("hyperref", Other), ("label", Other),
+    // These only ever show up in the body prose the grammar generates.
+    ("concerns", Verb), ("remains", Verb), ("unclear", Adjective), ("matter", Noun),
+    ("hand", Noun), ("happens", Verb), ("at", Other),
 ];
-/// Words that are left undefined.
-const UNDEFINED: &[&str] = &["the", "part", "of", "a", "that", "used", "in", "it", "you're", "doing", "where", "see", "with", "them", "i'm", "are", "not", "given", "this", "is", "once", "upon", "going", "through", "i", "get", "there", "doubt", "you'll", "an", "wish", "knew", "nil", "something", "nothing", "or", "and", "what's", "insert", "component", "here", "academia", "made", "more", "by", "being", "what", "out", "supposed", "to", "order", "do", "but", "shouldn't", "which", "said", "after", "dumb", "panicky", "best", "for", "dangerous", "animals", "kind", "getting", "silly", "distracting", "nature", "we", "written", "then", "comprising", "forewords", "left", "right", "all", "material", "hello", "following", "would", "need", "write", "these", "lucky", "your", "p", "s", "fun", "else", "really", "about", "ids", "was", "shared", "precisely", "than", "higher", "id", "section", "sections", "recommended", "community", "it's", "event", "you've", "heard", "mean", "when", "trust", "accuracy", "happening", "been", "just", "could", "entire", "purpose", "did", "chose", "happened", "my", "exists", "why", "submitted", "isn't", "low", "pretty", "rs", "main", "line", "wait", "can", "on", "appear", "message", "source", "book's", "into", "go", "have", "done", "playing", "they're", "character", "narrator", "reconcyl", "author", "text", "reader", "dear"];
 
 pub(super) fn get_global_glossary(arena: &mut WordArena) -> Glossary {
     let mut glossary = HashMap::new();
-    for (term, def) in DEFINED {
+    for (term, def, pos) in DEFINED {
         let term = arena.get(term);
+        arena.categorize(term, *pos);
         let def = AnnotatedString::new(arena, def.to_string());
         assert!(glossary.insert(term, Some(def)).is_none());
     }
-    for term in UNDEFINED {
+    for (term, pos) in UNDEFINED {
         let term = arena.get(term);
+        arena.categorize(term, *pos);
         assert!(glossary.insert(term, None).is_none());
     }
     // Make sure that the glossary is closed (it never uses a word without explicitly defining or not defining it)
@@ -90,4 +132,4 @@ pub(super) fn get_global_glossary(arena: &mut WordArena) -> Glossary {
         }
     }
     glossary
-}
\ No newline at end of file
+}
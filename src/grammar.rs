@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use rand::Rng;
+
+use super::WordArena;
+
+/// The name of a nonterminal in a [`Grammar`].
+pub(super) type NonTerminal = &'static str;
+
+/// One symbol in a production's right-hand side.
+pub(super) enum Symbol {
+    /// Literal text, copied into the output as-is.
+    Terminal(String),
+    /// A reference to another nonterminal, expanded recursively.
+    NonTerminal(NonTerminal),
+    /// A single word drawn from the arena, via [`WordArena::pick_random`].
+    PickRandomWord,
+}
+
+/// A weighted context-free grammar, mapping each nonterminal to the productions it may expand to.
+///
+/// Invariant: every nonterminal must have at least one production whose symbols contain no
+/// `NonTerminal`, so that expansion can always be forced to terminate.
+pub(super) struct Grammar {
+    productions: HashMap<NonTerminal, Vec<(u32, Vec<Symbol>)>>,
+}
+
+/// How many levels deep expansion is allowed to go before it is forced to bottom out.
+const MAX_DEPTH: u32 = 12;
+
+impl Grammar {
+    pub(super) fn new(productions: HashMap<NonTerminal, Vec<(u32, Vec<Symbol>)>>) -> Self {
+        Self { productions }
+    }
+
+    /// Expand `root` into a string, drawing random words from `arena` wherever a
+    /// [`Symbol::PickRandomWord`] is encountered.
+    pub(super) fn expand(&self, arena: &mut WordArena, root: NonTerminal) -> String {
+        let mut out = String::new();
+        self.expand_into(&mut out, arena, root, 0);
+        out
+    }
+
+    fn productions_of(&self, nt: NonTerminal) -> &[(u32, Vec<Symbol>)] {
+        self.productions.get(nt)
+            .unwrap_or_else(|| panic!("grammar has no productions for nonterminal '{}'", nt))
+    }
+
+    fn expand_into(&self, out: &mut String, arena: &mut WordArena, nt: NonTerminal, depth: u32) {
+        let productions = self.productions_of(nt);
+        let chosen = if depth >= MAX_DEPTH {
+            // Depth budget exhausted: force the lowest-weight production that contains no
+            // nonterminal, guaranteeing termination.
+            productions.iter()
+                .filter(|(_, symbols)| !symbols.iter().any(|s| matches!(s, Symbol::NonTerminal(_))))
+                .min_by_key(|(weight, _)| *weight)
+                .unwrap_or_else(|| panic!(
+                    "nonterminal '{}' has no terminal-only production to bottom out into", nt))
+        } else {
+            let total_weight: u32 = productions.iter().map(|(weight, _)| weight).sum();
+            let mut roll = rand::thread_rng().gen_range(0, total_weight);
+            productions.iter()
+                .find(|(weight, _)| {
+                    if roll < *weight {
+                        true
+                    } else {
+                        roll -= weight;
+                        false
+                    }
+                })
+                .expect("cumulative weights should cover the full range rolled")
+        };
+        for symbol in &chosen.1 {
+            match symbol {
+                Symbol::Terminal(text) => out.push_str(text),
+                Symbol::NonTerminal(name) => self.expand_into(out, arena, name, depth + 1),
+                Symbol::PickRandomWord => out.push_str(arena.pick_random()),
+            }
+        }
+    }
+}
@@ -9,14 +9,35 @@ use std::fmt::{self, Display};
 mod glossary;
 use glossary::Glossary;
 
+mod grammar;
+use grammar::{Grammar, Symbol};
+
+mod render;
+use render::{Renderer, MarkdownRenderer, HtmlRenderer, LatexRenderer};
+
+mod repl;
+
 type Word = id_arena::Id<String>;
 type SectionId = u16;
 
+/// A coarse part of speech, tagged onto glossary entries so words can be picked by grammatical
+/// role rather than completely at random.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum PartOfSpeech {
+    Noun,
+    Verb,
+    Adjective,
+    Adverb,
+    /// Anything that doesn't fit the other categories (articles, pronouns, prepositions, ...).
+    Other,
+}
+
 #[derive(Debug)]
 struct WordArena {
     arena: id_arena::Arena<String>,
     arena_id: Option<u32>,
     mapping: HashMap<String, Word>,
+    categories: HashMap<PartOfSpeech, BTreeSet<Word>>,
 }
 
 impl WordArena {
@@ -25,6 +46,7 @@ impl WordArena {
             arena: id_arena::Arena::new(),
             arena_id: None,
             mapping: HashMap::new(),
+            categories: HashMap::new(),
         }
     }
     fn get(&mut self, word: &str) -> Word {
@@ -41,9 +63,24 @@ impl WordArena {
     fn name(&self, id: Word) -> &str {
         self.arena.get(id).unwrap()
     }
-    fn pick_random(&self) -> &str {
+    /// Tag `word` as belonging to `cat`, so it becomes eligible for [`Self::pick_random_of_id`].
+    fn categorize(&mut self, word: Word, cat: PartOfSpeech) {
+        self.categories.entry(cat).or_default().insert(word);
+    }
+    fn pick_random_id(&self) -> Word {
         let idx = rand::thread_rng().gen_range(0, self.arena.len());
-        self.name(id_arena::DefaultArenaBehavior::new_id(self.arena_id.unwrap(), idx))
+        id_arena::DefaultArenaBehavior::new_id(self.arena_id.unwrap(), idx)
+    }
+    fn pick_random(&self) -> &str {
+        self.name(self.pick_random_id())
+    }
+    /// Pick a uniformly random word tagged with the given part of speech.
+    fn pick_random_of_id(&self, cat: PartOfSpeech) -> Word {
+        let words = self.categories.get(&cat)
+            .filter(|words| !words.is_empty())
+            .unwrap_or_else(|| panic!("no words are tagged as {:?}", cat));
+        let idx = rand::thread_rng().gen_range(0, words.len());
+        *words.iter().nth(idx).unwrap()
     }
 }
 
@@ -124,142 +161,239 @@ impl Display for Section {
     }
 }
 
-fn render_chapter_1(idg: &mut IdGenerator, arena: &mut WordArena) -> Section {
+/// Build the grammar used to generate the book's body prose.
+///
+/// Every nonterminal carries a low-weight, nonterminal-free production (the last one listed)
+/// so that [`Grammar::expand`] can always bottom out once its depth budget runs out.
+fn prose_grammar() -> Grammar {
+    let mut productions = HashMap::new();
+    productions.insert("SENTENCE", vec![
+        (5, vec![Symbol::NonTerminal("NP"), Symbol::Terminal(" ".into()), Symbol::NonTerminal("VP"), Symbol::Terminal(".".into())]),
+        (1, vec![Symbol::Terminal("Nothing happens.".into())]),
+    ]);
+    productions.insert("NP", vec![
+        (3, vec![Symbol::Terminal("the ".into()), Symbol::PickRandomWord]),
+        (1, vec![Symbol::Terminal("the matter at hand".into())]),
+    ]);
+    productions.insert("VP", vec![
+        (3, vec![Symbol::Terminal("concerns ".into()), Symbol::NonTerminal("NP")]),
+        (1, vec![Symbol::Terminal("remains unclear".into())]),
+    ]);
+    Grammar::new(productions)
+}
+
+fn render_chapter_1(idg: &mut IdGenerator, arena: &mut WordArena, renderer: &dyn Renderer) -> Section {
     Section::with_id(idg, SectionType::Chapter1, |id| {
-        let rendered = format!("## Chapter 1 (#{})\n\n\\<Insert academia joke here>", id);
+        let grammar = prose_grammar();
+        let mut rendered = renderer.heading("Chapter 1", id);
+        rendered.push_str("\n");
+        for _ in 0..rand::thread_rng().gen_range(3, 9) {
+            rendered.push_str(&grammar.expand(arena, "SENTENCE"));
+            rendered.push_str(" ");
+        }
         AnnotatedString::new(arena, rendered)
     })
 }
 
-fn render_dedication(idg: &mut IdGenerator, arena: &mut WordArena) -> Section {
+fn render_dedication(idg: &mut IdGenerator, arena: &mut WordArena, renderer: &dyn Renderer) -> Section {
     Section::with_id(idg, SectionType::Dedication, |id| {
-        let rendered = format!("## Dedication (#{0})\n\n\
-            All material following this dedication is dedicated to the NaNoGenMo 2019 community, \
-            with the exception of sections with an ID higher than this one (#{0}).", id);
+        let mut rendered = renderer.heading("Dedication", id);
+        rendered.push_str(&format!("\nAll material following this dedication is dedicated to the NaNoGenMo 2019 community, \
+            with the exception of sections with an ID higher than this one ({}).", renderer.section_ref(id)));
         AnnotatedString::new(arena, rendered)
     })
 }
 
-fn render_table_of_contents<'a>(idg: &mut IdGenerator, arena: &mut WordArena, sections: impl Iterator<Item=&'a Section>) -> Section {
-    Section::with_id(idg, SectionType::TableOfContents, |id| {
-        let mut rendered = format!("## Table of Contents (#{})\n", id);
-        for section in sections {
-            rendered.push_str(&format!("\n- **{}** (#{})", match section.type_ {
-                SectionType::Dedication => "Dedication",
-                SectionType::Fourword => "Fourword",
-                SectionType::TableOfContents => "Table of Contents",
-                SectionType::Chapter1 => "Chapter 1",
-                SectionType::Glossary => "Glossary",
-                SectionType::ListOfFigures => "List of Figures",
-                SectionType::Index => "Index",
-                SectionType::Afterword => "Afterword",
-            }, section.id));
-        }
-        AnnotatedString::new(arena, rendered)
-    })
+/// Render the content of a Table of Contents listing `sections`, independent of where the
+/// section itself lives in the deque. Shared between the initial render and later re-resolution.
+fn table_of_contents_content<'a>(
+    id: SectionId,
+    arena: &mut WordArena,
+    renderer: &dyn Renderer,
+    sections: impl Iterator<Item=&'a Section>,
+) -> AnnotatedString {
+    let mut rendered = renderer.heading("Table of Contents", id);
+    for section in sections {
+        let name = match section.type_ {
+            SectionType::Dedication => "Dedication",
+            SectionType::Fourword => "Fourword",
+            SectionType::TableOfContents => "Table of Contents",
+            SectionType::Chapter1 => "Chapter 1",
+            SectionType::Glossary => "Glossary",
+            SectionType::ListOfFigures => "List of Figures",
+            SectionType::Index => "Index",
+            SectionType::Afterword => "Afterword",
+        };
+        rendered.push_str(&renderer.list_item(&format!("{} ({})", renderer.bold(name), renderer.section_ref(section.id))));
+    }
+    AnnotatedString::new(arena, rendered)
 }
 
-fn render_fourword(idg: &mut IdGenerator, arena: &mut WordArena) -> Section {
+fn render_table_of_contents<'a>(
+    idg: &mut IdGenerator,
+    arena: &mut WordArena,
+    renderer: &dyn Renderer,
+    sections: impl Iterator<Item=&'a Section>,
+) -> Section {
+    Section::with_id(idg, SectionType::TableOfContents, |id| table_of_contents_content(id, arena, renderer, sections))
+}
+
+/// A handful of fixed shapes a "fourword" can take, each naming the parts of speech to draw from,
+/// in order.
+const FOURWORD_TEMPLATES: &[&[PartOfSpeech]] = &[
+    &[PartOfSpeech::Adjective, PartOfSpeech::Adjective, PartOfSpeech::Noun],
+    &[PartOfSpeech::Adverb, PartOfSpeech::Verb, PartOfSpeech::Adjective, PartOfSpeech::Noun],
+];
+
+fn render_fourword(idg: &mut IdGenerator, arena: &mut WordArena, renderer: &dyn Renderer) -> Section {
     Section::with_id(idg, SectionType::Fourword, |id| {
-        let mut rendered = format!("## Fourword (#{})\n\n", id);
-        // Select four random words from the arena.
-        rendered.push_str(&inflections::case::to_title_case(arena.pick_random()));
-        rendered.push_str(" ");
-        rendered.push_str(arena.pick_random());
-        rendered.push_str(" ");
-        rendered.push_str(arena.pick_random());
-        rendered.push_str(" ");
-        rendered.push_str(arena.pick_random());
+        const SEPARATOR: &str = " ";
+        let mut rendered = renderer.heading("Fourword", id);
+        rendered.push_str("\n");
+        let template = FOURWORD_TEMPLATES[rand::thread_rng().gen_range(0, FOURWORD_TEMPLATES.len())];
+        for (i, &cat) in template.iter().enumerate() {
+            if i != 0 {
+                rendered.push_str(SEPARATOR);
+            }
+            let word = arena.pick_random_of_id(cat);
+            let text = if i == 0 {
+                inflections::case::to_title_case(arena.name(word))
+            } else {
+                arena.name(word).to_string()
+            };
+            rendered.push_str(&renderer.word(arena, word, &text));
+        }
         rendered.push_str(".");
         AnnotatedString::new(arena, rendered)
     })
 }
 
+/// Render the content of a Glossary covering `sections`. Shared between the initial render and
+/// later re-resolution.
+fn glossary_content<'a>(
+    id: SectionId,
+    arena: &mut WordArena,
+    renderer: &dyn Renderer,
+    glossary: &Glossary,
+    sections: impl Iterator<Item=&'a Section>,
+) -> AnnotatedString {
+    let mut words = sections.flat_map(|section| &section.content.words).collect::<Vec<_>>();
+    words.sort();
+    words.dedup();
+    let mut rendered = renderer.heading("Glossary", id);
+    for &word in words.into_iter() {
+        if !glossary.contains_key(&word) {
+            panic!("'{}' is not defined", arena.name(word));
+        }
+        if let Some(def) = &glossary[&word] {
+            let mut entry = format!("{} - ", renderer.bold(&renderer.word(arena, word, arena.name(word))));
+            if def.content == glossary::RANDOM_SIGNAL {
+                let random_word = arena.pick_random_id();
+                entry.push_str(&format!("See '{}.'", renderer.word(arena, random_word, arena.name(random_word))));
+            } else {
+                entry.push_str(&def.content);
+            }
+            rendered.push_str(&renderer.list_item(&entry));
+        }
+    }
+    AnnotatedString::new(arena, rendered)
+}
+
 fn render_glossary<'a>(
     idg: &mut IdGenerator,
     arena: &mut WordArena,
-    glossary: &Glossary, 
+    renderer: &dyn Renderer,
+    glossary: &Glossary,
     sections: impl Iterator<Item=&'a Section>
 ) -> Section {
-    Section::with_id(idg, SectionType::Glossary, |id| {
-        let mut words = sections.flat_map(|section| &section.content.words).collect::<Vec<_>>();
-        words.sort();
-        words.dedup();
-        let mut rendered = format!("## Glossary (#{})\n", id);
-        for &word in words.into_iter() {
-            if !glossary.contains_key(&word) {
-                panic!("'{}' is not defined", arena.name(word));
-            }
-            if let Some(def) = &glossary[&word] {
-                rendered.push_str(&format!("\n- **{}** - ", arena.name(word)));
-                if def.content == glossary::RANDOM_SIGNAL {
-                    rendered.push_str(&format!("See '{}.'", arena.pick_random()));
-                } else {
-                    rendered.push_str(&def.content);
-                }
-            }
-        }
-        AnnotatedString::new(arena, rendered)
-    })
+    Section::with_id(idg, SectionType::Glossary, |id| glossary_content(id, arena, renderer, glossary, sections))
 }
 
 fn render_list_of_figures<'a>(
     idg: &mut IdGenerator,
     arena: &mut WordArena,
+    renderer: &dyn Renderer,
     random_section_id: SectionId,
 ) -> Section {
     Section::with_id(idg, SectionType::ListOfFigures, |id| {
-        let mut rendered = format!("## List of Figures (#{})\n", id);
+        let mut rendered = renderer.heading("List of Figures", id);
         let distribution = rand_distr::Normal::new(0.0, 3.0).unwrap();
         let quantity = rand::thread_rng().gen_range(5, 30);
         let mut note = false;
         for _ in 0..quantity {
-            rendered.push_str(&format!("\n- {:.3}", rand::thread_rng().sample(distribution)));
+            let mut entry = format!("{:.3}", rand::thread_rng().sample(distribution));
             if rand::thread_rng().gen_ratio(1, 10) {
-                rendered.push_str(" (*)");
+                entry.push_str(" (*)");
                 note = true;
             }
+            rendered.push_str(&renderer.list_item(&entry));
         }
         if note {
             rendered.push_str(&format!(
                 "\n\n(*) The accuracy of these numbers is not known. It is recommended not to trust them \
-                when reading section #{}.", random_section_id));
+                when reading section {}.", renderer.section_ref(random_section_id)));
         }
         AnnotatedString::new(arena, rendered)
     })
 }
 
-fn render_index<'a>(idg: &mut IdGenerator, arena: &mut WordArena, sections: impl Iterator<Item=&'a Section>) -> Section {
-    Section::with_id(idg, SectionType::Index, |id| {
-        let mut word_uses = BTreeMap::new();
-        for section in sections {
-            for &word in &section.content.words {
-                word_uses.entry(word).or_insert(BTreeSet::new()).insert(section.id);
-            }
+/// Render the content of an Index covering `sections`. Shared between the initial render and
+/// later re-resolution.
+fn index_content<'a>(
+    id: SectionId,
+    arena: &mut WordArena,
+    renderer: &dyn Renderer,
+    sections: impl Iterator<Item=&'a Section>,
+) -> AnnotatedString {
+    let sections: Vec<&Section> = sections.collect();
+    // A book can end up with several Index sections (whenever more than one is added; re-rendering
+    // replaces an Index in place). Only the lowest-ID Index anchors each word, so that `word`'s
+    // cross-references always have exactly one target to land on.
+    let anchors_words = !sections.iter()
+        .any(|section| matches!(section.type_, SectionType::Index) && section.id < id);
+    let mut word_uses = BTreeMap::new();
+    for section in &sections {
+        for &word in &section.content.words {
+            word_uses.entry(word).or_insert(BTreeSet::new()).insert(section.id);
         }
-        let mut rendered = format!("## Index (#{})\n", id);
-        for (word, use_set) in word_uses {
-            rendered.push_str("\n- **");
-            rendered.push_str(arena.name(word));
-            rendered.push_str("** - ");
-            for (i, id) in use_set.into_iter().enumerate() {
-                if i != 0 {
-                    rendered.push_str(", ");
-                }
-                rendered.push_str(&format!("#{}", id));
+    }
+    let mut rendered = renderer.heading("Index", id);
+    for (word, use_set) in word_uses {
+        let headword = if anchors_words {
+            renderer.word_anchor(arena, word, arena.name(word))
+        } else {
+            renderer.word(arena, word, arena.name(word))
+        };
+        let mut entry = format!("{} - ", renderer.bold(&headword));
+        for (i, section_id) in use_set.into_iter().enumerate() {
+            if i != 0 {
+                entry.push_str(", ");
             }
+            entry.push_str(&renderer.section_ref(section_id));
         }
-        AnnotatedString::new(arena, rendered)
-    })
+        rendered.push_str(&renderer.list_item(&entry));
+    }
+    AnnotatedString::new(arena, rendered)
+}
+
+fn render_index<'a>(
+    idg: &mut IdGenerator,
+    arena: &mut WordArena,
+    renderer: &dyn Renderer,
+    sections: impl Iterator<Item=&'a Section>,
+) -> Section {
+    Section::with_id(idg, SectionType::Index, |id| index_content(id, arena, renderer, sections))
 }
 
 fn render_afterword<'a>(
     idg: &mut IdGenerator,
     arena: &mut WordArena,
+    renderer: &dyn Renderer,
     mut random_section_id: impl FnMut() -> SectionId,
 ) -> Section {
     Section::with_id(idg, SectionType::Afterword, |id| {
-        let mut rendered = format!("## Afterword (#{})\n\n", id);
+        let mut rendered = renderer.heading("Afterword", id);
+        rendered.push_str("\n");
         if rand::thread_rng().gen_ratio(1, 10_000_000) {
             rendered.push_str(
                 "Hello, dear reader! I'm the author of the text you're reading. Not @Reconcyl, but the narrator. The character \
@@ -281,23 +415,76 @@ fn render_afterword<'a>(
                 } else {
                     ", "
                 });
-                rendered.push_str(&format!("#{:?}", random_section_id()))
+                rendered.push_str(&renderer.section_ref(random_section_id()));
             }
             rendered.push_str(".");
         } else {
-            rendered.push_str(&inflections::case::to_title_case(arena.pick_random()));
+            let grammar = prose_grammar();
+            rendered.push_str(&grammar.expand(arena, "SENTENCE"));
         }
         AnnotatedString::new(arena, rendered)
     })
 }
 
+/// A handle onto a [`Section`] already sitting in the deque, allowing it to be re-rendered in
+/// place without moving or reallocating any other entry.
+struct MutableSection<'a> {
+    slot: &'a mut Section,
+}
+
+impl<'a> MutableSection<'a> {
+    fn id(&self) -> SectionId {
+        self.slot.id
+    }
+    fn type_(&self) -> SectionType {
+        self.slot.type_
+    }
+    fn set_content(&mut self, content: AnnotatedString) {
+        self.slot.content = content;
+    }
+}
+
+/// Re-render the Table of Contents, Glossary, and Index sections now that every section has been
+/// allocated, so that they reflect the finished book rather than just the sections that existed
+/// when they were first generated.
+///
+/// The Index is resolved last, so that it also picks up any words the Glossary's re-rendered
+/// definitions bring in.
+fn resolve_references(sections: &mut VecDeque<Section>, arena: &mut WordArena, renderer: &dyn Renderer, glossary: &Glossary) {
+    let toc_and_glossary: Vec<usize> = sections.iter().enumerate()
+        .filter(|(_, section)| matches!(section.type_, SectionType::TableOfContents | SectionType::Glossary))
+        .map(|(i, _)| i)
+        .collect();
+    for i in toc_and_glossary {
+        let (id, type_) = {
+            let handle = MutableSection { slot: &mut sections[i] };
+            (handle.id(), handle.type_())
+        };
+        let content = match type_ {
+            SectionType::TableOfContents => table_of_contents_content(id, arena, renderer, sections.iter()),
+            SectionType::Glossary => glossary_content(id, arena, renderer, glossary, sections.iter()),
+            _ => unreachable!(),
+        };
+        MutableSection { slot: &mut sections[i] }.set_content(content);
+    }
+    let index_positions: Vec<usize> = sections.iter().enumerate()
+        .filter(|(_, section)| matches!(section.type_, SectionType::Index))
+        .map(|(i, _)| i)
+        .collect();
+    for i in index_positions {
+        let id = MutableSection { slot: &mut sections[i] }.id();
+        let content = index_content(id, arena, renderer, sections.iter());
+        MutableSection { slot: &mut sections[i] }.set_content(content);
+    }
+}
+
 const GENERATE_TYPES: u8 = 7;
-fn generate(word_minimum: usize) -> VecDeque<Section> {
+fn generate(word_minimum: usize, renderer: &dyn Renderer) -> VecDeque<Section> {
     let mut arena = WordArena::new();
     let glossary = glossary::get_global_glossary(&mut arena);
     let mut sections = VecDeque::new();
     let mut idg = IdGenerator(BTreeSet::new());
-    sections.push_back(render_chapter_1(&mut idg, &mut arena));
+    sections.push_back(render_chapter_1(&mut idg, &mut arena, renderer));
     let rng_range = rand::distributions::Uniform::from(0..GENERATE_TYPES);
     let random_section_id = |sections: &VecDeque<Section>| {
         let slices = sections.as_slices();
@@ -314,46 +501,64 @@ fn generate(word_minimum: usize) -> VecDeque<Section> {
     } {
         match rand::thread_rng().sample(rng_range) {
             0 => {
-                let section = render_dedication(&mut idg, &mut arena);
+                let section = render_dedication(&mut idg, &mut arena, renderer);
                 sections.push_front(section);
             }
             1 => {
-                let section = render_fourword(&mut idg, &mut arena);
+                let section = render_fourword(&mut idg, &mut arena, renderer);
                 sections.push_front(section);
             }
             2 => {
-                let section = render_table_of_contents(&mut idg, &mut arena, sections.iter());
+                let section = render_table_of_contents(&mut idg, &mut arena, renderer, sections.iter());
                 sections.push_front(section);
             }
             3 => {
-                let section = render_glossary(&mut idg, &mut arena, &glossary, sections.iter());
+                let section = render_glossary(&mut idg, &mut arena, renderer, &glossary, sections.iter());
                 sections.push_back(section);
             }
             4 => {
-                let section = render_list_of_figures(&mut idg, &mut arena, random_section_id(&sections));
+                let section = render_list_of_figures(&mut idg, &mut arena, renderer, random_section_id(&sections));
                 sections.push_back(section);
             }
             5 => {
-                let section = render_index(&mut idg, &mut arena, sections.iter());
+                let section = render_index(&mut idg, &mut arena, renderer, sections.iter());
                 sections.push_back(section);
             }
             6 => {
-                let section = render_afterword(&mut idg, &mut arena, || random_section_id(&sections));
+                let section = render_afterword(&mut idg, &mut arena, renderer, || random_section_id(&sections));
                 sections.push_back(section);
             }
             _ => unreachable!(),
         }
     }
+    resolve_references(&mut sections, &mut arena, renderer, &glossary);
     sections
 }
 
+/// Pick an output backend from a `--format=<markdown|html|latex>` CLI flag, defaulting to Markdown.
+fn renderer_from_args() -> Box<dyn Renderer> {
+    let format = std::env::args()
+        .find_map(|arg| arg.strip_prefix("--format=").map(str::to_string));
+    match format.as_deref() {
+        Some("html") => Box::new(HtmlRenderer),
+        Some("latex") => Box::new(LatexRenderer),
+        Some("markdown") | None => Box::new(MarkdownRenderer),
+        Some(other) => panic!("unknown output format '{}'", other),
+    }
+}
+
 fn main() {
+    let renderer = renderer_from_args();
+    if std::env::args().any(|arg| arg == "--repl") {
+        repl::run(&*renderer);
+        return;
+    }
     let mut result = String::new();
-    for (i, section) in generate(50_000).into_iter().enumerate() {
+    for (i, section) in generate(50_000, &*renderer).into_iter().enumerate() {
         if i != 0 {
             result.push_str("\n\n");
         }
         result.push_str(&section.content.content);
     }
     println!("{}", result);
-}
\ No newline at end of file
+}
@@ -0,0 +1,88 @@
+use super::{SectionId, Word, WordArena};
+
+/// Formats the structural building blocks of a section into a particular output syntax.
+///
+/// `word` is used to mark up an individual word mention (e.g. from [`WordArena::pick_random_id`])
+/// as a cross-reference to that word's Index entry; `word_anchor` marks up that same word at the
+/// Index entry itself, so the cross-reference has somewhere to land. The other methods format
+/// the generic structural elements every section is built from.
+pub(super) trait Renderer {
+    fn heading(&self, text: &str, id: SectionId) -> String;
+    fn bold(&self, text: &str) -> String;
+    fn list_item(&self, text: &str) -> String;
+    fn section_ref(&self, id: SectionId) -> String;
+    fn word(&self, arena: &WordArena, word: Word, text: &str) -> String;
+    fn word_anchor(&self, arena: &WordArena, word: Word, text: &str) -> String;
+}
+
+/// Emits the plain Markdown this crate has always produced.
+pub(super) struct MarkdownRenderer;
+
+impl Renderer for MarkdownRenderer {
+    fn heading(&self, text: &str, id: SectionId) -> String {
+        format!("## {} (#{})\n", text, id)
+    }
+    fn bold(&self, text: &str) -> String {
+        format!("**{}**", text)
+    }
+    fn list_item(&self, text: &str) -> String {
+        format!("\n- {}", text)
+    }
+    fn section_ref(&self, id: SectionId) -> String {
+        format!("#{}", id)
+    }
+    fn word(&self, _arena: &WordArena, _word: Word, text: &str) -> String {
+        text.to_string()
+    }
+    fn word_anchor(&self, _arena: &WordArena, _word: Word, text: &str) -> String {
+        text.to_string()
+    }
+}
+
+/// Emits a navigable HTML fragment, turning section and word references into anchors.
+pub(super) struct HtmlRenderer;
+
+impl Renderer for HtmlRenderer {
+    fn heading(&self, text: &str, id: SectionId) -> String {
+        format!("<h2 id=\"section-{0}\">{1} ({2})</h2>\n", id, text, self.section_ref(id))
+    }
+    fn bold(&self, text: &str) -> String {
+        format!("<b>{}</b>", text)
+    }
+    fn list_item(&self, text: &str) -> String {
+        format!("<li>{}</li>\n", text)
+    }
+    fn section_ref(&self, id: SectionId) -> String {
+        format!("<a href=\"#section-{0}\">#{0}</a>", id)
+    }
+    fn word(&self, arena: &WordArena, word: Word, text: &str) -> String {
+        format!("<a href=\"#word-{}\">{}</a>", arena.name(word), text)
+    }
+    fn word_anchor(&self, arena: &WordArena, word: Word, text: &str) -> String {
+        format!("<a id=\"word-{}\">{}</a>", arena.name(word), text)
+    }
+}
+
+/// Emits LaTeX, turning section and word references into `\hyperref` links.
+pub(super) struct LatexRenderer;
+
+impl Renderer for LatexRenderer {
+    fn heading(&self, text: &str, id: SectionId) -> String {
+        format!("\\section{{{} ({})}}\n\\label{{section:{}}}\n", text, self.section_ref(id), id)
+    }
+    fn bold(&self, text: &str) -> String {
+        format!("\\textbf{{{}}}", text)
+    }
+    fn list_item(&self, text: &str) -> String {
+        format!("\\item {}\n", text)
+    }
+    fn section_ref(&self, id: SectionId) -> String {
+        format!("\\hyperref[section:{0}]{{\\#{0}}}", id)
+    }
+    fn word(&self, arena: &WordArena, word: Word, text: &str) -> String {
+        format!("\\hyperref[word:{}]{{{}}}", arena.name(word), text)
+    }
+    fn word_anchor(&self, arena: &WordArena, word: Word, text: &str) -> String {
+        format!("\\label{{word:{}}}{}", arena.name(word), text)
+    }
+}
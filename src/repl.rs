@@ -0,0 +1,162 @@
+use std::collections::{BTreeSet, VecDeque};
+use std::io::{self, BufRead, Write};
+
+use super::render::Renderer;
+use super::{
+    glossary, render_afterword, render_chapter_1, render_dedication, render_fourword,
+    render_glossary, render_index, render_list_of_figures, render_table_of_contents,
+    resolve_references, Glossary, IdGenerator, Section, SectionId, Word, WordArena,
+};
+
+/// The state an interactive session keeps alive between commands.
+struct ReplState {
+    arena: WordArena,
+    glossary: Glossary,
+    idg: IdGenerator,
+    sections: VecDeque<Section>,
+}
+
+impl ReplState {
+    fn new() -> Self {
+        let mut arena = WordArena::new();
+        let glossary = glossary::get_global_glossary(&mut arena);
+        Self { arena, glossary, idg: IdGenerator(BTreeSet::new()), sections: VecDeque::new() }
+    }
+    fn word_count(&self) -> usize {
+        self.sections.iter().map(Section::word_count).sum()
+    }
+    /// Pick the ID of a uniformly random existing section, for sections that reference another.
+    fn random_section_id(&self) -> Option<SectionId> {
+        let slices = self.sections.as_slices();
+        let total = slices.0.len() + slices.1.len();
+        if total == 0 {
+            return None;
+        }
+        let idx = rand::Rng::gen_range(&mut rand::thread_rng(), 0, total);
+        Some(if idx < slices.0.len() { slices.0[idx].id } else { slices.1[idx - slices.0.len()].id })
+    }
+}
+
+/// Run the interactive REPL, reading commands from stdin until it's closed.
+///
+/// A line ending in `\` is a continuation: the next line is appended to it before the command is
+/// evaluated, so multi-line input (e.g. a long grammar snippet) can be pasted across several
+/// lines.
+pub(super) fn run(renderer: &dyn Renderer) {
+    let mut state = ReplState::new();
+    let stdin = io::stdin();
+    let mut buffer = String::new();
+    loop {
+        print!("{}", if buffer.is_empty() { "> " } else { ".. " });
+        io::stdout().flush().unwrap();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+        let line = line.trim_end_matches('\n');
+        if let Some(continued) = line.strip_suffix('\\') {
+            buffer.push_str(continued);
+            buffer.push('\n');
+            continue;
+        }
+        buffer.push_str(line);
+        let command = std::mem::take(&mut buffer);
+        handle_command(&mut state, renderer, command.trim());
+    }
+}
+
+fn handle_command(state: &mut ReplState, renderer: &dyn Renderer, command: &str) {
+    let mut words = command.split_whitespace();
+    match words.next() {
+        Some("dedication") => {
+            let section = render_dedication(&mut state.idg, &mut state.arena, renderer);
+            state.sections.push_front(section);
+        }
+        Some("fourword") => {
+            let section = render_fourword(&mut state.idg, &mut state.arena, renderer);
+            state.sections.push_front(section);
+        }
+        Some("toc") => {
+            let section = render_table_of_contents(&mut state.idg, &mut state.arena, renderer, state.sections.iter());
+            state.sections.push_front(section);
+        }
+        Some("chapter1") => {
+            let section = render_chapter_1(&mut state.idg, &mut state.arena, renderer);
+            state.sections.push_back(section);
+        }
+        Some("glossary") => {
+            let section = render_glossary(&mut state.idg, &mut state.arena, renderer, &state.glossary, state.sections.iter());
+            state.sections.push_back(section);
+        }
+        Some("figures") => {
+            let random_section_id = match state.random_section_id() {
+                Some(id) => id,
+                None => { println!("add another section first, so the note has something to point to"); return; }
+            };
+            let section = render_list_of_figures(&mut state.idg, &mut state.arena, renderer, random_section_id);
+            state.sections.push_back(section);
+        }
+        Some("index") => {
+            let section = render_index(&mut state.idg, &mut state.arena, renderer, state.sections.iter());
+            state.sections.push_back(section);
+        }
+        Some("afterword") => {
+            let section = {
+                let arena = &mut state.arena;
+                let sections = &state.sections;
+                render_afterword(&mut state.idg, arena, renderer, || {
+                    let slices = sections.as_slices();
+                    let idx = rand::Rng::gen_range(&mut rand::thread_rng(), 0, slices.0.len() + slices.1.len());
+                    if idx < slices.0.len() { slices.0[idx].id } else { slices.1[idx - slices.0.len()].id }
+                })
+            };
+            state.sections.push_back(section);
+        }
+        Some("words") => println!("{} words so far", state.word_count()),
+        Some("dump") => match words.next().and_then(|s| s.parse::<SectionId>().ok()) {
+            Some(id) => match state.sections.iter().find(|section| section.id == id) {
+                Some(section) => println!("{}", section),
+                None => println!("no section with ID {}", id),
+            },
+            None => println!("usage: dump <id>"),
+        },
+        Some("find") => match words.next() {
+            Some(word) => find_word(state, word),
+            None => println!("usage: find <word>"),
+        },
+        Some("export") => {
+            resolve_references(&mut state.sections, &mut state.arena, renderer, &state.glossary);
+            let mut result = String::new();
+            for (i, section) in state.sections.iter().enumerate() {
+                if i != 0 {
+                    result.push_str("\n\n");
+                }
+                result.push_str(&section.content.content);
+            }
+            println!("{}", result);
+        }
+        Some(other) => println!("unknown command: {}", other),
+        None => {}
+    }
+}
+
+/// Report which sections use `word`, reusing the same word/section bookkeeping as the Index.
+fn find_word(state: &ReplState, word: &str) {
+    let lower = word.to_lowercase();
+    let word: Word = match state.arena.mapping.get(&lower) {
+        Some(&word) => word,
+        None => { println!("'{}' never appears in this book", word); return; }
+    };
+    let mut use_set = BTreeSet::new();
+    for section in &state.sections {
+        if section.content.words.contains(&word) {
+            use_set.insert(section.id);
+        }
+    }
+    if use_set.is_empty() {
+        println!("'{}' is known but doesn't occur in any section yet", lower);
+    } else {
+        let ids: Vec<String> = use_set.into_iter().map(|id| format!("#{}", id)).collect();
+        println!("'{}' occurs in: {}", lower, ids.join(", "));
+    }
+}